@@ -1,5 +1,5 @@
 use criterion::{BenchmarkId, Criterion, criterion_group, criterion_main};
-use minacalc_rs::{Calc, CalcMode, Note};
+use minacalc_rs::{Calc, CalcMode, Note, ScoreGoal};
 
 /// Generate a simple stream of alternating columns at a given NPS.
 fn stream(note_count: usize, nps: f32) -> Vec<Note> {
@@ -17,11 +17,21 @@ fn bench_calc_at_rate(c: &mut Criterion) {
     let notes = stream(500, 8.0);
 
     c.bench_function("calc_at_rate/1.0x SSR", |b| {
-        b.iter(|| calc.calc_at_rate(&notes, 1.0, 0.93, 4, CalcMode::Ssr).unwrap());
+        b.iter(|| {
+            calc.calc_at_rate(
+                &notes,
+                1.0,
+                4,
+                CalcMode::Ssr {
+                    score_goal: ScoreGoal::DEFAULT,
+                },
+            )
+            .unwrap()
+        });
     });
 
     c.bench_function("calc_at_rate/1.5x MSD", |b| {
-        b.iter(|| calc.calc_at_rate(&notes, 1.5, 0.93, 4, CalcMode::Msd).unwrap());
+        b.iter(|| calc.calc_at_rate(&notes, 1.5, 4, CalcMode::Msd).unwrap());
     });
 }
 
@@ -30,7 +40,16 @@ fn bench_calc_all_rates(c: &mut Criterion) {
     let notes = stream(500, 8.0);
 
     c.bench_function("calc_all_rates/SSR", |b| {
-        b.iter(|| calc.calc_all_rates(&notes, 4, CalcMode::Ssr).unwrap());
+        b.iter(|| {
+            calc.calc_all_rates(
+                &notes,
+                4,
+                CalcMode::Ssr {
+                    score_goal: ScoreGoal::DEFAULT,
+                },
+            )
+            .unwrap()
+        });
     });
 
     c.bench_function("calc_all_rates/MSD", |b| {
@@ -17,8 +17,10 @@ fn main() {
         .expect("calc failed");
 
     println!("MSD for all rates:");
-    for (i, scores) in all.rates.iter().enumerate() {
-        let rate = 0.7 + i as f32 * 0.1;
-        println!("  {:.1}x  overall: {:.2}  stream: {:.2}", rate, scores.overall, scores.stream);
+    for (rate, scores) in &all {
+        println!(
+            "  {:.1}  overall: {:.2}  stream: {:.2}",
+            rate, scores.overall, scores.stream
+        );
     }
 }
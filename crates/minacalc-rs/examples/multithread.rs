@@ -1,4 +1,4 @@
-use minacalc_rs::{Calc, CalcMode, Note};
+use minacalc_rs::{Calc, CalcMode, Note, ScoreGoal};
 use std::thread;
 
 /// Calc is !Send — each thread owns its own instance.
@@ -17,7 +17,14 @@ fn main() {
                 let calc = Calc::new().expect("failed to create calculator");
                 let rate = 0.8 + i as f32 * 0.2;
                 let scores = calc
-                    .calc_at_rate(&notes, rate, 0.93, 4, CalcMode::Ssr)
+                    .calc_at_rate(
+                        &notes,
+                        rate,
+                        4,
+                        CalcMode::Ssr {
+                            score_goal: ScoreGoal::DEFAULT,
+                        },
+                    )
                     .expect("calc failed");
                 (rate, scores.overall)
             })
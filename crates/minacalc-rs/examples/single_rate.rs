@@ -1,4 +1,4 @@
-use minacalc_rs::{Calc, CalcMode, Note};
+use minacalc_rs::{Calc, CalcMode, Note, ScoreGoal};
 
 fn main() {
     let calc = Calc::new().expect("failed to create calculator");
@@ -14,7 +14,14 @@ fn main() {
 
     // SSR: score-relative difficulty at 1.0x, score goal 93%
     let ssr = calc
-        .calc_at_rate(&notes, 1.0, 0.93, 4, CalcMode::Ssr)
+        .calc_at_rate(
+            &notes,
+            1.0,
+            4,
+            CalcMode::Ssr {
+                score_goal: ScoreGoal::DEFAULT,
+            },
+        )
         .expect("calc failed");
 
     println!("SSR @ 1.0x");
@@ -25,7 +32,7 @@ fn main() {
 
     // MSD: raw difficulty at 1.5x
     let msd = calc
-        .calc_at_rate(&notes, 1.5, 0.93, 4, CalcMode::Msd)
+        .calc_at_rate(&notes, 1.5, 4, CalcMode::Msd)
         .expect("calc failed");
 
     println!("MSD @ 1.5x");
@@ -1,4 +1,4 @@
-use minacalc_rs::{Calc, CalcMode, Note};
+use minacalc_rs::{Calc, CalcMode, Note, Rate, ScoreGoal};
 use std::time::Instant;
 
 fn gen_notes(count: usize, nps: f32, jitter: f32) -> Vec<Note> {
@@ -24,12 +24,21 @@ fn main() {
     println!("=== realistic chart ({} notes, ~5min, 8 NPS) ===", chart.len());
 
     let t = Instant::now();
-    let ssr = calc.calc_at_rate(&chart, 1.0, 0.93, 4, CalcMode::Ssr).unwrap();
+    let ssr = calc
+        .calc_at_rate(
+            &chart,
+            1.0,
+            4,
+            CalcMode::Ssr {
+                score_goal: ScoreGoal::DEFAULT,
+            },
+        )
+        .unwrap();
     println!("calc_at_rate  1.0x SSR : overall={:.2}  ({:.2?})", ssr.overall, t.elapsed());
 
     let t = Instant::now();
     let all = calc.calc_all_rates(&chart, 4, CalcMode::Msd).unwrap();
-    let overall_at_1x = all.rates[3].overall; // index 3 = 1.0x
+    let overall_at_1x = all.at(Rate::ONE).unwrap().overall;
     println!("calc_all_rates    MSD  : overall@1.0x={:.2}  ({:.2?})", overall_at_1x, t.elapsed());
 
     // stress: max valid size (~50 000s at 8 NPS = 400k notes, but C++ caps at 100k intervals = 50 000s)
@@ -38,10 +47,23 @@ fn main() {
     println!("\n=== stress chart ({} notes, ~7h, 8 NPS) ===", stress.len());
 
     let t = Instant::now();
-    let ssr = calc.calc_at_rate(&stress, 1.0, 0.93, 4, CalcMode::Ssr).unwrap();
+    let ssr = calc
+        .calc_at_rate(
+            &stress,
+            1.0,
+            4,
+            CalcMode::Ssr {
+                score_goal: ScoreGoal::DEFAULT,
+            },
+        )
+        .unwrap();
     println!("calc_at_rate  1.0x SSR : overall={:.2}  ({:.2?})", ssr.overall, t.elapsed());
 
     let t = Instant::now();
     let all = calc.calc_all_rates(&stress, 4, CalcMode::Msd).unwrap();
-    println!("calc_all_rates    MSD  : overall@1.0x={:.2}  ({:.2?})", all.rates[3].overall, t.elapsed());
+    println!(
+        "calc_all_rates    MSD  : overall@1.0x={:.2}  ({:.2?})",
+        all.at(Rate::ONE).unwrap().overall,
+        t.elapsed()
+    );
 }
@@ -0,0 +1,148 @@
+//! Density data over time, for charting alongside the difficulty numbers.
+
+use crate::types::Note;
+
+/// Note and chord counts for one window of a chart.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct DensityPoint {
+    /// Window start in seconds.
+    pub time: f32,
+    /// Individual notes in the window, a jump counts as two.
+    pub notes: usize,
+    /// Rows with two or more notes in the window.
+    pub chords: usize,
+    /// `notes` divided by the window length.
+    pub nps: f32,
+}
+
+/// Most points [`density`] will return; smaller windows give nothing.
+pub const MAX_DENSITY_POINTS: usize = 100_000;
+
+/// Split the chart into back-to-back windows of `window_secs` starting at
+/// the first row, one [`DensityPoint`] per window, empty windows included.
+///
+/// Assumes rows are sorted by time. Returns nothing for empty notes, a
+/// non-positive or NaN window, or a window so small the chart would need
+/// more than [`MAX_DENSITY_POINTS`] of them.
+#[must_use]
+pub fn density(notes: &[Note], window_secs: f32) -> Vec<DensityPoint> {
+    let (Some(first), Some(last)) = (notes.first(), notes.last()) else {
+        return Vec::new();
+    };
+    if window_secs.is_nan() || window_secs <= 0.0 {
+        return Vec::new();
+    }
+    let windows = (last.row_time - first.row_time) / window_secs;
+    if windows.is_nan() || windows >= MAX_DENSITY_POINTS as f32 {
+        return Vec::new();
+    }
+
+    let count = windows as usize + 1;
+    let mut points: Vec<DensityPoint> = (0..count)
+        .map(|i| DensityPoint {
+            time: first.row_time + i as f32 * window_secs,
+            ..DensityPoint::default()
+        })
+        .collect();
+    for n in notes {
+        let i = (((n.row_time - first.row_time) / window_secs) as usize).min(count - 1);
+        let size = n.notes.count_ones() as usize;
+        points[i].notes += size;
+        if size >= 2 {
+            points[i].chords += 1;
+        }
+    }
+    for p in &mut points {
+        p.nps = p.notes as f32 / window_secs;
+    }
+    points
+}
+
+/// Render `points` as a standalone SVG area graph of NPS, with chord
+/// counts per second drawn as a line on the same scale.
+///
+/// `window_secs` is the window `points` were computed with by [`density`].
+#[cfg(feature = "plot")]
+#[must_use]
+pub fn density_svg(points: &[DensityPoint], window_secs: f32, width: u32, height: u32) -> String {
+    use std::fmt::Write;
+
+    let (w, h) = (width as f32, height as f32);
+    let mut svg = String::new();
+    let _ = write!(
+        svg,
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="{width}" height="{height}" viewBox="0 0 {width} {height}">"#
+    );
+    if let (Some(first), Some(last)) = (points.first(), points.last()) {
+        let span = (last.time - first.time).max(f32::EPSILON);
+        let peak = points.iter().map(|p| p.nps).fold(0.0, f32::max).max(1.0);
+        let x = |p: &DensityPoint| (p.time - first.time) / span * w;
+        let y = |v: f32| h - v / peak * h;
+
+        let mut area = format!("0,{h} ");
+        let mut chords = String::new();
+        for p in points {
+            let _ = write!(area, "{:.1},{:.1} ", x(p), y(p.nps));
+            let _ = write!(
+                chords,
+                "{:.1},{:.1} ",
+                x(p),
+                y(p.chords as f32 / window_secs)
+            );
+        }
+        let _ = write!(area, "{w},{h}");
+        let _ = write!(
+            svg,
+            r##"<polygon points="{area}" fill="#4a90d9" fill-opacity="0.6"/><polyline points="{}" fill="none" stroke="#d9534f" stroke-width="1"/>"##,
+            chords.trim_end()
+        );
+    }
+    svg.push_str("</svg>");
+    svg
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn density_splits_into_windows() {
+        let notes = [
+            Note {
+                notes: 0b0001,
+                row_time: 1.0,
+            },
+            Note {
+                notes: 0b0011,
+                row_time: 1.5,
+            },
+            Note {
+                notes: 0b0100,
+                row_time: 3.2,
+            },
+        ];
+        let points = density(&notes, 1.0);
+        let counts: Vec<_> = points.iter().map(|p| (p.time, p.notes, p.chords)).collect();
+        assert_eq!(counts, [(1.0, 3, 1), (2.0, 0, 0), (3.0, 1, 0)]);
+        assert_eq!(points[0].nps, 3.0);
+        assert!(density(&notes, 0.0).is_empty());
+        assert!(density(&notes, f32::NAN).is_empty());
+    }
+
+    #[test]
+    fn density_caps_point_count() {
+        let notes = [
+            Note {
+                notes: 0b0001,
+                row_time: 0.0,
+            },
+            Note {
+                notes: 0b0010,
+                row_time: 300.0,
+            },
+        ];
+        assert!(density(&notes, 1e-9).is_empty());
+        assert!(density(&notes, 0.001).is_empty());
+        assert_eq!(density(&notes, 0.5).len(), 601);
+    }
+}
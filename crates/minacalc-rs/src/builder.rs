@@ -0,0 +1,105 @@
+use crate::error::Error;
+use crate::types::Note;
+
+/// Builds [`Note`] rows from per-column taps.
+///
+/// Handles the bitmask packing, sorting by time and merging of taps that
+/// land at the same time into one row.
+///
+/// ```
+/// use minacalc_rs::NotesBuilder;
+///
+/// let notes = NotesBuilder::new()
+///     .tap(0, 0.0)
+///     .chord(&[1, 2], 0.25)
+///     .tap(3, 0.25)
+///     .finish();
+/// assert_eq!(notes.len(), 2);
+/// assert_eq!(notes[1].notes, 0b1110);
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct NotesBuilder {
+    rows: Vec<Note>,
+}
+
+impl NotesBuilder {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a single tap on `column` (0-based) at `time` seconds.
+    ///
+    /// # Panics
+    /// Panics if `column` is 32 or more.
+    pub fn tap(&mut self, column: u32, time: f32) -> &mut Self {
+        self.chord(&[column], time)
+    }
+
+    /// Add taps on every column in `columns` at `time` seconds.
+    ///
+    /// # Panics
+    /// Panics if any column is 32 or more.
+    pub fn chord(&mut self, columns: &[u32], time: f32) -> &mut Self {
+        let notes = columns.iter().fold(0, |mask, &column| {
+            assert!(column < 32, "column {column} out of range (max 31)");
+            mask | 1 << column
+        });
+        if notes != 0 {
+            self.rows.push(Note {
+                notes,
+                row_time: time,
+            });
+        }
+        self
+    }
+
+    /// Rows sorted by time, taps at the same time merged into one row.
+    #[must_use]
+    pub fn finish(&self) -> Vec<Note> {
+        let mut rows = self.rows.clone();
+        rows.sort_by(|a, b| a.row_time.total_cmp(&b.row_time));
+        rows.dedup_by(|next, prev| {
+            if next.row_time == prev.row_time {
+                prev.notes |= next.notes;
+                true
+            } else {
+                false
+            }
+        });
+        rows
+    }
+}
+
+/// Rows from `(column, time)` events, rejecting columns outside `0..keys`.
+///
+/// Rows are 32-bit masks, so columns past 31 are rejected whatever `keys` says.
+pub(crate) fn notes_from_events(events: &[(u8, f32)], keys: u32) -> Result<Vec<Note>, Error> {
+    let keys = keys.min(32);
+    let mut builder = NotesBuilder::new();
+    for &(column, time) in events {
+        let column = u32::from(column);
+        if column >= keys {
+            return Err(Error::ColumnOutOfRange { column, keys });
+        }
+        builder.tap(column, time);
+    }
+    Ok(builder.finish())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn events_past_bitmask_width_are_rejected() {
+        assert!(matches!(
+            notes_from_events(&[(0, 0.0), (40, 0.5)], 64),
+            Err(Error::ColumnOutOfRange {
+                column: 40,
+                keys: 32
+            })
+        ));
+        assert!(notes_from_events(&[(31, 0.0)], 64).is_ok());
+    }
+}
@@ -1,6 +1,15 @@
+use std::borrow::Cow;
+
+use crate::builder::notes_from_events;
 use crate::error::Error;
-use crate::types::{AllRates, CalcMode, Note, SkillsetScores};
-use minacalc_sys::CalcHandle;
+use crate::ffi::{self, CalcHandle, NoteInfo};
+use crate::preprocess::NotePreprocessor;
+use crate::types::{
+    AllRates, CalcMode, Note, Rate, RatedChart, ScoreGoal, SkillsetScores, StaminaCurve,
+};
+
+/// Most intervals the calc ever reports (`max_intervals` in MinaCalc.h).
+const MAX_INTERVALS: usize = 100_000;
 
 /// Safe RAII wrapper around the `MinaCalc` calculator.
 ///
@@ -8,83 +17,357 @@ use minacalc_sys::CalcHandle;
 /// Instantiate one per thread.
 pub struct Calc {
     handle: *mut CalcHandle,
+    preprocessor: Option<NotePreprocessor>,
 }
 
 impl Calc {
     /// # Errors
     /// Returns [`Error::AllocationFailed`] if the C++ allocator returns null.
     pub fn new() -> Result<Self, Error> {
-        let handle = unsafe { minacalc_sys::create_calc() };
+        let handle = unsafe { ffi::create_calc() };
         if handle.is_null() {
             return Err(Error::AllocationFailed);
         }
-        Ok(Self { handle })
+        Ok(Self {
+            handle,
+            preprocessor: None,
+        })
+    }
+
+    /// Run `preprocessor` over the notes passed to every calc method.
+    ///
+    /// Off by default: notes are handed to the calculator as-is.
+    #[must_use]
+    pub fn with_preprocessor(mut self, preprocessor: NotePreprocessor) -> Self {
+        self.preprocessor = Some(preprocessor);
+        self
     }
 
     #[must_use]
     pub fn version() -> i32 {
-        unsafe { minacalc_sys::calc_version() }
+        unsafe { ffi::calc_version() }
     }
 
     /// Calculate difficulty at a single rate.
     ///
     /// - `notes`: rows of note data
     /// - `rate`: music rate (e.g. 1.0 for 1x)
-    /// - `goal`: score goal, only relevant for [`CalcMode::Ssr`] (typically 0.93)
     /// - `keys`: key count (4, 6, or 7)
-    /// - `mode`: [`CalcMode::Msd`] for raw difficulty, [`CalcMode::Ssr`] for score-relative
+    /// - `mode`: [`CalcMode::Msd`] for raw difficulty, [`CalcMode::Ssr`] for
+    ///   score-relative at its `score_goal` (typically [`ScoreGoal::DEFAULT`], 93%)
     ///
     /// # Errors
-    /// Returns [`Error::EmptyNotes`] if `notes` is empty.
+    /// Returns [`Error::EmptyNotes`] if `notes` is empty, or any error from the
+    /// preprocessor set with [`Calc::with_preprocessor`].
     pub fn calc_at_rate(
         &self,
         notes: &[Note],
         rate: f32,
-        goal: f32,
         keys: u32,
         mode: CalcMode,
     ) -> Result<SkillsetScores, Error> {
-        if notes.is_empty() {
-            return Err(Error::EmptyNotes);
+        let notes = self.prepare(notes)?;
+        Ok(self.rate_prepared(&notes, rate, keys, mode))
+    }
+
+    /// Like [`Calc::calc_at_rate`], but returns the scores bundled with the
+    /// rate, mode, key count, note count and length they belong to.
+    ///
+    /// # Errors
+    /// Same as [`Calc::calc_at_rate`].
+    pub fn rate_chart(
+        &self,
+        notes: &[Note],
+        rate: f32,
+        keys: u32,
+        mode: CalcMode,
+    ) -> Result<RatedChart, Error> {
+        let notes = self.prepare(notes)?;
+        let scores = self.rate_prepared(&notes, rate, keys, mode);
+        let first = notes[0].row_time;
+        let last = notes[notes.len() - 1].row_time;
+        Ok(RatedChart {
+            scores,
+            rate: Rate::new(rate),
+            mode,
+            keys,
+            note_count: notes.iter().map(|n| n.notes.count_ones() as usize).sum(),
+            length: last - first,
+            chartkey: None,
+        })
+    }
+
+    /// Calculate difficulty for all rates (0.7x to 2.0x).
+    ///
+    /// # Errors
+    /// Returns [`Error::EmptyNotes`] if `notes` is empty, or any error from the
+    /// preprocessor set with [`Calc::with_preprocessor`].
+    pub fn calc_all_rates(
+        &self,
+        notes: &[Note],
+        keys: u32,
+        mode: CalcMode,
+    ) -> Result<AllRates, Error> {
+        let notes = self.prepare(notes)?;
+        let mut raw: Vec<NoteInfo> = notes.iter().map(|&n| n.into()).collect();
+        match mode {
+            CalcMode::Msd => {
+                let result = unsafe {
+                    ffi::calc_all_rates(self.handle, raw.as_ptr(), raw.len(), keys, mode.into())
+                };
+                Ok(result.into())
+            }
+            // the C++ all-rates path always rates at 93%, go rate by rate to honour the goal
+            CalcMode::Ssr { score_goal } => {
+                let mut all = AllRates {
+                    rates: [SkillsetScores::default(); 14],
+                };
+                for (i, scores) in all.rates.iter_mut().enumerate() {
+                    let result = unsafe {
+                        ffi::calc_at_rate(
+                            self.handle,
+                            raw.as_mut_ptr(),
+                            raw.len(),
+                            Rate::from_index(i).value(),
+                            score_goal.fraction(),
+                            keys,
+                            mode.into(),
+                        )
+                    };
+                    *scores = result.into();
+                }
+                Ok(all)
+            }
         }
-        let mut raw: Vec<minacalc_sys::NoteInfo> = notes.iter().map(|&n| n.into()).collect();
+    }
+
+    /// Like [`Calc::calc_at_rate`], but takes `(column, time)` events.
+    ///
+    /// Events may be unsorted; taps sharing a time are merged into one row.
+    ///
+    /// # Errors
+    /// Returns [`Error::EmptyNotes`] if `events` is empty,
+    /// [`Error::ColumnOutOfRange`] if a column is not below `keys`, or any
+    /// error from the preprocessor set with [`Calc::with_preprocessor`].
+    pub fn calc_at_rate_from_events(
+        &self,
+        events: &[(u8, f32)],
+        rate: f32,
+        keys: u32,
+        mode: CalcMode,
+    ) -> Result<SkillsetScores, Error> {
+        let notes = notes_from_events(events, keys)?;
+        self.calc_at_rate(&notes, rate, keys, mode)
+    }
+
+    /// Like [`Calc::calc_all_rates`], but takes `(column, time)` events.
+    ///
+    /// # Errors
+    /// Same as [`Calc::calc_at_rate_from_events`].
+    pub fn calc_all_rates_from_events(
+        &self,
+        events: &[(u8, f32)],
+        keys: u32,
+        mode: CalcMode,
+    ) -> Result<AllRates, Error> {
+        let notes = notes_from_events(events, keys)?;
+        self.calc_all_rates(&notes, keys, mode)
+    }
+
+    /// Per-hand stamina multiplier over time at a single rate.
+    ///
+    /// Runs the calc's debug pass (SSR mode), which is noticeably slower than
+    /// [`Calc::calc_at_rate`]. Charts the calc skips (too short, junk timing)
+    /// yield an empty curve.
+    ///
+    /// # Errors
+    /// Returns [`Error::InvalidRate`] unless `rate` is positive and finite,
+    /// [`Error::EmptyNotes`] if `notes` is empty, or any error from the
+    /// preprocessor set with [`Calc::with_preprocessor`].
+    pub fn stamina_curve(
+        &self,
+        notes: &[Note],
+        rate: f32,
+        goal: ScoreGoal,
+        keys: u32,
+    ) -> Result<StaminaCurve, Error> {
+        if !(rate.is_finite() && rate > 0.0) {
+            return Err(Error::InvalidRate { rate });
+        }
+        let notes = self.prepare(notes)?;
+        let last = notes[notes.len() - 1];
+        let raw: Vec<NoteInfo> = notes.iter().map(|&n| n.into()).collect();
+        // a guess, the calc reports the real count and we retry if it was short
+        let mut capacity = ((last.row_time / rate / StaminaCurve::INTERVAL).max(0.0) as usize)
+            .saturating_add(2)
+            .min(MAX_INTERVALS);
+        loop {
+            let mut curve = StaminaCurve {
+                left: vec![0.0; capacity],
+                right: vec![0.0; capacity],
+            };
+            let count = unsafe {
+                ffi::calc_stamina_curve(
+                    self.handle,
+                    raw.as_ptr(),
+                    raw.len(),
+                    rate,
+                    goal.fraction(),
+                    keys,
+                    curve.left.as_mut_ptr(),
+                    curve.right.as_mut_ptr(),
+                    capacity,
+                )
+            };
+            if count <= capacity {
+                curve.left.truncate(count);
+                curve.right.truncate(count);
+                return Ok(curve);
+            }
+            capacity = count;
+        }
+    }
+
+    /// Single-rate calc over rows already passed through [`Calc::prepare`].
+    fn rate_prepared(
+        &self,
+        notes: &[Note],
+        rate: f32,
+        keys: u32,
+        mode: CalcMode,
+    ) -> SkillsetScores {
+        let mut raw: Vec<NoteInfo> = notes.iter().map(|&n| n.into()).collect();
         let result = unsafe {
-            minacalc_sys::calc_at_rate(
+            ffi::calc_at_rate(
                 self.handle,
                 raw.as_mut_ptr(),
                 raw.len(),
                 rate,
-                goal,
+                mode.score_goal().fraction(),
                 keys,
                 mode.into(),
             )
         };
-        Ok(result.into())
+        result.into()
     }
 
-    /// Calculate difficulty for all rates (0.7x to 2.0x).
-    ///
-    /// # Errors
-    /// Returns [`Error::EmptyNotes`] if `notes` is empty.
-    pub fn calc_all_rates(
-        &self,
-        notes: &[Note],
-        keys: u32,
-        mode: CalcMode,
-    ) -> Result<AllRates, Error> {
+    fn prepare<'a>(&self, notes: &'a [Note]) -> Result<Cow<'a, [Note]>, Error> {
+        let notes = match &self.preprocessor {
+            Some(p) => Cow::Owned(p.process(notes)?.notes),
+            None => Cow::Borrowed(notes),
+        };
         if notes.is_empty() {
             return Err(Error::EmptyNotes);
         }
-        let raw: Vec<minacalc_sys::NoteInfo> = notes.iter().map(|&n| n.into()).collect();
-        let result = unsafe {
-            minacalc_sys::calc_all_rates(self.handle, raw.as_ptr(), raw.len(), keys, mode.into())
-        };
-        Ok(result.into())
+        Ok(notes)
     }
 }
 
 impl Drop for Calc {
     fn drop(&mut self) {
-        unsafe { minacalc_sys::destroy_calc(self.handle) }
+        unsafe { ffi::destroy_calc(self.handle) }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn stream(seconds: f32) -> Vec<Note> {
+        (0..(seconds * 8.0) as usize)
+            .map(|i| Note {
+                notes: 1 << (i % 4),
+                row_time: i as f32 / 8.0,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn ssr_all_rates_matches_calc_at_default_goal() {
+        let calc = Calc::new().unwrap();
+        let notes = stream(60.0);
+        let mode = CalcMode::Ssr {
+            score_goal: ScoreGoal::DEFAULT,
+        };
+        let all = calc.calc_all_rates(&notes, 4, mode).unwrap();
+        let raw: Vec<NoteInfo> = notes.iter().map(|&n| n.into()).collect();
+        let expected: AllRates =
+            unsafe { ffi::calc_all_rates(calc.handle, raw.as_ptr(), raw.len(), 4, mode.into()) }
+                .into();
+        assert_eq!(all.rates, expected.rates);
+    }
+
+    #[test]
+    fn ssr_all_rates_honours_goal() {
+        let calc = Calc::new().unwrap();
+        let notes = stream(60.0);
+        let at = |percent| {
+            let score_goal = ScoreGoal::from_percent(percent).unwrap();
+            let all = calc
+                .calc_all_rates(&notes, 4, CalcMode::Ssr { score_goal })
+                .unwrap();
+            all.at(Rate::ONE).unwrap().overall
+        };
+        assert!(at(96.0) > at(93.0));
+    }
+
+    #[test]
+    fn stamina_curve_covers_rated_length() {
+        let calc = Calc::new().unwrap();
+        let notes = stream(60.0);
+        let length = notes[notes.len() - 1].row_time;
+        for rate in [1.0, 1.5] {
+            let curve = calc
+                .stamina_curve(&notes, rate, ScoreGoal::DEFAULT, 4)
+                .unwrap();
+            let expected = length / rate / StaminaCurve::INTERVAL;
+            assert!(
+                (curve.len() as f32 - expected).abs() <= 2.0,
+                "{} intervals at {rate}x, expected about {expected}",
+                curve.len()
+            );
+            assert_eq!(curve.left.len(), curve.right.len());
+        }
+    }
+
+    #[test]
+    fn stamina_curve_single_row_is_empty() {
+        let calc = Calc::new().unwrap();
+        let notes = [Note {
+            notes: 0b0001,
+            row_time: 0.0,
+        }];
+        let curve = calc
+            .stamina_curve(&notes, 1.0, ScoreGoal::DEFAULT, 4)
+            .unwrap();
+        assert!(curve.is_empty());
+    }
+
+    #[test]
+    fn stamina_curve_far_off_last_row() {
+        let calc = Calc::new().unwrap();
+        for far in [1e13, f32::MAX] {
+            let mut notes = stream(10.0);
+            notes.push(Note {
+                notes: 0b0001,
+                row_time: far,
+            });
+            let curve = calc
+                .stamina_curve(&notes, 1.0, ScoreGoal::DEFAULT, 4)
+                .unwrap();
+            assert!(curve.is_empty());
+        }
+    }
+
+    #[test]
+    fn stamina_curve_rejects_bad_rates() {
+        let calc = Calc::new().unwrap();
+        let notes = stream(10.0);
+        for rate in [0.0, -1.0, f32::NAN, f32::INFINITY] {
+            assert!(matches!(
+                calc.stamina_curve(&notes, rate, ScoreGoal::DEFAULT, 4),
+                Err(Error::InvalidRate { .. })
+            ));
+        }
     }
 }
@@ -0,0 +1,63 @@
+//! Text rendering for results, shared by anything printing scores.
+
+use std::fmt;
+
+use crate::types::{AllRates, Skillset, SkillsetScores};
+
+/// Two-column table, one skillset per line.
+impl fmt::Display for SkillsetScores {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let precision = f.precision().unwrap_or(2);
+        for (i, (ss, value)) in self.iter().enumerate() {
+            if i > 0 {
+                writeln!(f)?;
+            }
+            write!(f, "{:<10}  {:>6.*}", ss.name(), precision, value)?;
+        }
+        Ok(())
+    }
+}
+
+/// One row per rate, one column per skillset.
+impl fmt::Display for AllRates {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let precision = f.precision().unwrap_or(2);
+        write!(f, "{:<5}", "rate")?;
+        for ss in Skillset::ALL {
+            write!(f, "  {:>10}", ss.name())?;
+        }
+        for (rate, scores) in self {
+            let label = format!("{rate:.1}");
+            write!(f, "\n{label:<5}")?;
+            for (_, value) in scores.iter() {
+                write!(f, "  {:>10.*}", precision, value)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Single-line rendering of [`SkillsetScores`], created by [`SkillsetScores::compact`].
+#[derive(Debug, Clone, Copy)]
+pub struct Compact<'a>(&'a SkillsetScores);
+
+impl SkillsetScores {
+    /// Render on one line, e.g. `overall 24.31 | stream 22.10 | …`.
+    #[must_use]
+    pub fn compact(&self) -> Compact<'_> {
+        Compact(self)
+    }
+}
+
+impl fmt::Display for Compact<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let precision = f.precision().unwrap_or(2);
+        for (i, (ss, value)) in self.0.iter().enumerate() {
+            if i > 0 {
+                f.write_str(" | ")?;
+            }
+            write!(f, "{} {:.*}", ss.name(), precision, value)?;
+        }
+        Ok(())
+    }
+}
@@ -1,20 +1,92 @@
-use std::fmt;
+use thiserror::Error;
 
-#[derive(Debug)]
+use crate::types::Rate;
+
+#[derive(Debug, Error)]
+#[non_exhaustive]
 pub enum Error {
     /// C++ calc allocation failed
+    #[error("failed to allocate calculator")]
     AllocationFailed,
     /// Notes slice was empty
+    #[error("notes slice is empty")]
     EmptyNotes,
+    /// An event referenced a column outside `0..keys`
+    #[error("column {column} out of range for {keys}K")]
+    ColumnOutOfRange { column: u32, keys: u32 },
+    /// Row `index` is earlier than the row before it
+    #[error("notes are not sorted by time (row {index})")]
+    UnsortedNotes { index: usize },
+    /// Score goal outside the valid percent/fraction range
+    #[error("invalid score goal {value}")]
+    InvalidScoreGoal { value: f32 },
+    /// Rate lookup didn't match any covered rate
+    #[error("rate {rate}x is not available (supported: {})", list_rates(.supported))]
+    UnsupportedRate { rate: f32, supported: Vec<Rate> },
+    /// Music rate was zero, negative or not finite
+    #[error("invalid music rate {rate}")]
+    InvalidRate { rate: f32 },
+    /// Key count has no matching StepMania steps type
+    #[error("no .sm steps type for {keys}K")]
+    UnsupportedKeyCount { keys: u32 },
+    /// Replay has a different number of hits than the chart has notes
+    #[error("replay has {actual} hits but the chart has {expected} notes")]
+    ReplayLengthMismatch { expected: usize, actual: usize },
+}
+
+fn list_rates(rates: &[Rate]) -> String {
+    rates
+        .iter()
+        .map(|r| format!("{r:.1}"))
+        .collect::<Vec<_>>()
+        .join(", ")
 }
 
-impl fmt::Display for Error {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+impl Error {
+    /// Machine-readable category of this error.
+    #[must_use]
+    pub fn code(&self) -> ErrorCode {
         match self {
-            Error::AllocationFailed => write!(f, "failed to allocate calculator"),
-            Error::EmptyNotes => write!(f, "notes slice is empty"),
+            Error::AllocationFailed => ErrorCode::AllocationFailed,
+            Error::EmptyNotes => ErrorCode::EmptyNotes,
+            Error::ColumnOutOfRange { .. } => ErrorCode::ColumnOutOfRange,
+            Error::UnsortedNotes { .. } => ErrorCode::UnsortedNotes,
+            Error::InvalidScoreGoal { .. } => ErrorCode::InvalidScoreGoal,
+            Error::UnsupportedRate { .. } => ErrorCode::UnsupportedRate,
+            Error::ReplayLengthMismatch { .. } => ErrorCode::ReplayLengthMismatch,
+            Error::InvalidRate { .. } => ErrorCode::InvalidRate,
+            Error::UnsupportedKeyCount { .. } => ErrorCode::UnsupportedKeyCount,
         }
     }
 }
 
-impl std::error::Error for Error {}
+/// Stable numeric error category, for FFI layers and other bindings.
+///
+/// Discriminants never change once released; new variants get new numbers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+#[repr(i32)]
+pub enum ErrorCode {
+    AllocationFailed = 1,
+    EmptyNotes = 2,
+    ColumnOutOfRange = 3,
+    UnsortedNotes = 4,
+    InvalidScoreGoal = 5,
+    UnsupportedRate = 6,
+    ReplayLengthMismatch = 7,
+    InvalidRate = 8,
+    UnsupportedKeyCount = 9,
+}
+
+impl ErrorCode {
+    #[must_use]
+    pub const fn as_i32(self) -> i32 {
+        self as i32
+    }
+}
+
+impl From<ErrorCode> for i32 {
+    fn from(code: ErrorCode) -> Self {
+        code.as_i32()
+    }
+}
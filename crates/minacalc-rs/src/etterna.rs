@@ -0,0 +1,94 @@
+//! Conversions to and from the [`etterna`] crate's skillset types.
+
+use crate::types::{Skillset, SkillsetScores};
+
+impl From<SkillsetScores> for etterna::Skillsets8 {
+    fn from(s: SkillsetScores) -> Self {
+        Self {
+            overall: s.overall,
+            stream: s.stream,
+            jumpstream: s.jumpstream,
+            handstream: s.handstream,
+            stamina: s.stamina,
+            jackspeed: s.jackspeed,
+            chordjack: s.chordjack,
+            technical: s.technical,
+        }
+    }
+}
+
+impl From<etterna::Skillsets8> for SkillsetScores {
+    fn from(s: etterna::Skillsets8) -> Self {
+        Self {
+            overall: s.overall,
+            stream: s.stream,
+            jumpstream: s.jumpstream,
+            handstream: s.handstream,
+            stamina: s.stamina,
+            jackspeed: s.jackspeed,
+            chordjack: s.chordjack,
+            technical: s.technical,
+        }
+    }
+}
+
+impl From<Skillset> for etterna::Skillset8 {
+    fn from(s: Skillset) -> Self {
+        match s {
+            Skillset::Overall => Self::Overall,
+            Skillset::Stream => Self::Stream,
+            Skillset::Jumpstream => Self::Jumpstream,
+            Skillset::Handstream => Self::Handstream,
+            Skillset::Stamina => Self::Stamina,
+            Skillset::Jackspeed => Self::Jackspeed,
+            Skillset::Chordjack => Self::Chordjack,
+            Skillset::Technical => Self::Technical,
+        }
+    }
+}
+
+impl From<etterna::Skillset8> for Skillset {
+    fn from(s: etterna::Skillset8) -> Self {
+        match s {
+            etterna::Skillset8::Overall => Self::Overall,
+            etterna::Skillset8::Stream => Self::Stream,
+            etterna::Skillset8::Jumpstream => Self::Jumpstream,
+            etterna::Skillset8::Handstream => Self::Handstream,
+            etterna::Skillset8::Stamina => Self::Stamina,
+            etterna::Skillset8::Jackspeed => Self::Jackspeed,
+            etterna::Skillset8::Chordjack => Self::Chordjack,
+            etterna::Skillset8::Technical => Self::Technical,
+        }
+    }
+}
+
+impl From<etterna::Skillset7> for Skillset {
+    fn from(s: etterna::Skillset7) -> Self {
+        etterna::Skillset8::from(s).into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn skillsets_round_trip() {
+        let scores = SkillsetScores {
+            overall: 8.0,
+            stream: 1.0,
+            jumpstream: 2.0,
+            handstream: 3.0,
+            stamina: 4.0,
+            jackspeed: 5.0,
+            chordjack: 6.0,
+            technical: 7.0,
+        };
+        let theirs = etterna::Skillsets8::from(scores);
+        for skillset in Skillset::ALL {
+            assert_eq!(theirs.get(skillset.into()), scores.get(skillset));
+            assert_eq!(Skillset::from(etterna::Skillset8::from(skillset)), skillset);
+        }
+        assert_eq!(SkillsetScores::from(theirs), scores);
+    }
+}
@@ -0,0 +1,248 @@
+//! Write notes back out as chart files, to check what the calc was fed.
+
+use std::fmt::Write;
+
+use crate::error::Error;
+use crate::types::Note;
+
+/// A tempo change: `bpm` from `time` seconds onwards.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BpmSection {
+    pub time: f32,
+    pub bpm: f32,
+}
+
+impl BpmSection {
+    /// Tempo used by [`to_sm`] when no sections are given.
+    pub const DEFAULT_BPM: f32 = 120.0;
+}
+
+/// Finest snap written out, in rows per measure.
+const ROWS_PER_MEASURE: usize = 192;
+
+/// Render `notes` as a StepMania `.sm` file with a single Edit chart.
+///
+/// `bpm_sections` must be sorted by time; the first one is taken to start
+/// at 0s, and an empty slice means a constant [`BpmSection::DEFAULT_BPM`].
+/// Rows are snapped to 192nds, taps landing on the same snap are merged
+/// and columns not below `key_count` are left out. Rows before 0s land on
+/// the first beat, so shift the chart first (see
+/// [`NotePreprocessor`](crate::NotePreprocessor)) if it starts early.
+///
+/// # Errors
+/// Returns [`Error::UnsupportedKeyCount`] unless `key_count` is one of 3 to
+/// 10, the key counts with a StepMania steps type.
+pub fn to_sm(notes: &[Note], bpm_sections: &[BpmSection], key_count: u32) -> Result<String, Error> {
+    let steps_type = steps_type(key_count).ok_or(Error::UnsupportedKeyCount { keys: key_count })?;
+    let default = [BpmSection {
+        time: 0.0,
+        bpm: BpmSection::DEFAULT_BPM,
+    }];
+    let sections = if bpm_sections.is_empty() {
+        &default[..]
+    } else {
+        bpm_sections
+    };
+
+    // first section starts at 0s whatever its time says
+    let start = |i: usize| if i == 0 { 0.0 } else { sections[i].time };
+    let mut starts = Vec::with_capacity(sections.len());
+    let mut beat = 0.0_f64;
+    for i in 0..sections.len() {
+        if i > 0 {
+            beat += f64::from(start(i) - start(i - 1)) * f64::from(sections[i - 1].bpm) / 60.0;
+        }
+        starts.push(beat);
+    }
+
+    let mask = (1 << key_count) - 1;
+    let mut rows: Vec<(usize, u32)> = notes
+        .iter()
+        .filter(|n| n.notes & mask != 0)
+        .map(|n| {
+            let i = sections
+                .iter()
+                .rposition(|s| s.time <= n.row_time)
+                .unwrap_or(0);
+            let beat =
+                starts[i] + f64::from(n.row_time - start(i)) * f64::from(sections[i].bpm) / 60.0;
+            let row = (beat.max(0.0) * (ROWS_PER_MEASURE / 4) as f64).round() as usize;
+            (row, n.notes & mask)
+        })
+        .collect();
+    rows.sort_by_key(|&(row, _)| row);
+    rows.dedup_by(|next, prev| {
+        if next.0 == prev.0 {
+            prev.1 |= next.1;
+            true
+        } else {
+            false
+        }
+    });
+
+    let mut sm = String::new();
+    let _ = writeln!(sm, "#TITLE:minacalc-rs export;");
+    let _ = writeln!(sm, "#OFFSET:0.000000;");
+    let bpms: Vec<String> = sections
+        .iter()
+        .zip(&starts)
+        .map(|(s, beat)| format!("{beat:.6}={:.6}", s.bpm))
+        .collect();
+    let _ = writeln!(sm, "#BPMS:{};", bpms.join(","));
+    let _ = writeln!(sm, "#NOTES:");
+    let _ = writeln!(sm, "     {steps_type}:");
+    let _ = writeln!(sm, "     :");
+    let _ = writeln!(sm, "     Edit:");
+    let _ = writeln!(sm, "     1:");
+    let _ = writeln!(sm, "     0,0,0,0,0:");
+
+    let measures = rows
+        .last()
+        .map_or(1, |&(row, _)| row / ROWS_PER_MEASURE + 1);
+    let mut rows = rows.into_iter().peekable();
+    for measure in 0..measures {
+        let mut in_measure = Vec::new();
+        while let Some(&(row, bits)) = rows.peek() {
+            if row / ROWS_PER_MEASURE != measure {
+                break;
+            }
+            in_measure.push((row % ROWS_PER_MEASURE, bits));
+            rows.next();
+        }
+        // coarsest snap that still holds every row of the measure
+        let lines = [4, 8, 12, 16, 24, 32, 48, 64, 96, ROWS_PER_MEASURE]
+            .into_iter()
+            .find(|&lines| {
+                in_measure
+                    .iter()
+                    .all(|&(row, _)| row % (ROWS_PER_MEASURE / lines) == 0)
+            })
+            .unwrap_or(ROWS_PER_MEASURE);
+        let step = ROWS_PER_MEASURE / lines;
+        let mut in_measure = in_measure.into_iter().peekable();
+        for line in 0..lines {
+            let bits = match in_measure.peek() {
+                Some(&(row, bits)) if row == line * step => {
+                    in_measure.next();
+                    bits
+                }
+                _ => 0,
+            };
+            for column in 0..key_count {
+                sm.push(if bits & 1 << column != 0 { '1' } else { '0' });
+            }
+            sm.push('\n');
+        }
+        sm.push_str(if measure + 1 == measures {
+            ";\n"
+        } else {
+            ",\n"
+        });
+    }
+    Ok(sm)
+}
+
+fn steps_type(key_count: u32) -> Option<&'static str> {
+    Some(match key_count {
+        3 => "dance-threepanel",
+        4 => "dance-single",
+        5 => "pump-single",
+        6 => "dance-solo",
+        7 => "kb7-single",
+        8 => "dance-double",
+        9 => "pnm-nine",
+        10 => "pump-double",
+        _ => return None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn to_sm_snaps_rows_in_tempo_grid() {
+        let notes = [
+            Note {
+                notes: 0b0001,
+                row_time: 0.0,
+            },
+            Note {
+                notes: 0b0010,
+                row_time: 0.25,
+            },
+            Note {
+                notes: 0b1100,
+                row_time: 0.5,
+            },
+            // 240 BPM from 2s (beat 4): 2.125s is beat 4.5, an 8th
+            Note {
+                notes: 0b0001,
+                row_time: 2.125,
+            },
+            Note {
+                notes: 0b1000,
+                row_time: 3.0,
+            },
+        ];
+        let sections = [
+            BpmSection {
+                time: 0.0,
+                bpm: 120.0,
+            },
+            BpmSection {
+                time: 2.0,
+                bpm: 240.0,
+            },
+        ];
+        let expected = "\
+#TITLE:minacalc-rs export;
+#OFFSET:0.000000;
+#BPMS:0.000000=120.000000,4.000000=240.000000;
+#NOTES:
+     dance-single:
+     :
+     Edit:
+     1:
+     0,0,0,0,0:
+1000
+0100
+0011
+0000
+0000
+0000
+0000
+0000
+,
+0000
+1000
+0000
+0000
+0000
+0000
+0000
+0000
+,
+0001
+0000
+0000
+0000
+;
+";
+        assert_eq!(to_sm(&notes, &sections, 4).unwrap(), expected);
+    }
+
+    #[test]
+    fn to_sm_rejects_key_counts_without_steps_type() {
+        let notes = [Note {
+            notes: 1,
+            row_time: 0.0,
+        }];
+        for keys in [0, 2, 11, 40] {
+            assert!(matches!(
+                to_sm(&notes, &[], keys),
+                Err(Error::UnsupportedKeyCount { .. })
+            ));
+        }
+    }
+}
@@ -0,0 +1,54 @@
+//! Raw `minacalc-sys` bindings, for advanced users.
+//!
+//! Everything here is `unsafe` and mirrors the C API one-to-one; prefer
+//! [`Calc`](crate::Calc). Re-exported so callers don't need a separate (and
+//! possibly mismatched) `minacalc-sys` dependency. The `From` impls converting
+//! to and from the safe types also live here.
+
+pub use minacalc_sys::{
+    calc_all_rates, calc_at_rate, calc_stamina_curve, calc_version, create_calc, destroy_calc,
+    CalcHandle, CalcMode, MsdForAllRates, NoteInfo, Ssr,
+};
+
+use crate::types::{AllRates, Note, SkillsetScores};
+
+impl From<Note> for NoteInfo {
+    fn from(n: Note) -> Self {
+        NoteInfo {
+            notes: n.notes,
+            rowTime: n.row_time,
+        }
+    }
+}
+
+impl From<Ssr> for SkillsetScores {
+    fn from(s: Ssr) -> Self {
+        Self {
+            overall: s.overall,
+            stream: s.stream,
+            jumpstream: s.jumpstream,
+            handstream: s.handstream,
+            stamina: s.stamina,
+            jackspeed: s.jackspeed,
+            chordjack: s.chordjack,
+            technical: s.technical,
+        }
+    }
+}
+
+impl From<MsdForAllRates> for AllRates {
+    fn from(m: MsdForAllRates) -> Self {
+        Self {
+            rates: m.msds.map(SkillsetScores::from),
+        }
+    }
+}
+
+impl From<crate::CalcMode> for CalcMode {
+    fn from(m: crate::CalcMode) -> Self {
+        match m {
+            crate::CalcMode::Msd => CalcMode::MSD,
+            crate::CalcMode::Ssr { .. } => CalcMode::SSR,
+        }
+    }
+}
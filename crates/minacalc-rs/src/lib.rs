@@ -1,7 +1,26 @@
+pub mod analysis;
+mod builder;
 mod calc;
+mod display;
 mod error;
+#[cfg(feature = "etterna")]
+mod etterna;
+pub mod export;
+pub mod ffi;
+pub mod prelude;
+mod preprocess;
+pub mod scoring;
+mod stats;
+pub mod transforms;
 mod types;
 
+pub use builder::NotesBuilder;
 pub use calc::Calc;
-pub use error::Error;
-pub use types::{AllRates, CalcMode, Note, SkillsetScores};
+pub use display::Compact;
+pub use error::{Error, ErrorCode};
+pub use preprocess::{NotePreprocessor, Preprocessed};
+pub use stats::ChartStats;
+pub use types::{
+    AllRates, AllRatesIter, CalcMode, Note, Rate, RatedChart, ScoreGoal, Skillset, SkillsetScores,
+    StaminaCurve,
+};
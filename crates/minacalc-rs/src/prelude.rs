@@ -0,0 +1,6 @@
+//! Common imports: `use minacalc_rs::prelude::*;`
+
+pub use crate::{
+    AllRates, Calc, CalcMode, Error, ErrorCode, Note, NotePreprocessor, NotesBuilder, Rate,
+    ScoreGoal, Skillset, SkillsetScores,
+};
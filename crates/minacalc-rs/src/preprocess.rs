@@ -0,0 +1,147 @@
+use crate::error::Error;
+use crate::types::Note;
+
+/// Opt-in cleanup pass for note rows before they reach the calculator.
+///
+/// The calc assumes rows are sorted, unique in time and non-empty; anything
+/// else silently skews the result. Enable on a [`Calc`](crate::Calc) with
+/// [`Calc::with_preprocessor`](crate::Calc::with_preprocessor), or run
+/// [`NotePreprocessor::process`] directly to see what was changed.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct NotePreprocessor {
+    /// Sort rows by time. When off, unsorted input is an error instead.
+    pub sort: bool,
+    /// Merge rows sharing the same time into one row.
+    pub merge_duplicates: bool,
+    /// Drop rows with no columns set.
+    pub drop_empty: bool,
+    /// Shift the chart so the first row is at 0s if it starts before that.
+    pub clamp_negative: bool,
+    /// Shift the chart so the first row lands exactly here (seconds), also
+    /// trimming leading silence. Takes precedence over `clamp_negative`.
+    pub start_at: Option<f32>,
+}
+
+impl Default for NotePreprocessor {
+    fn default() -> Self {
+        Self {
+            sort: true,
+            merge_duplicates: true,
+            drop_empty: true,
+            clamp_negative: true,
+            start_at: None,
+        }
+    }
+}
+
+/// Output of [`NotePreprocessor::process`].
+#[derive(Debug, Clone, Default)]
+pub struct Preprocessed {
+    pub notes: Vec<Note>,
+    /// Seconds added to every row time (negative when leading silence was trimmed).
+    pub offset: f32,
+    /// Whether the input had to be reordered.
+    pub reordered: bool,
+    /// Rows folded into an earlier row with the same time.
+    pub merged: usize,
+    /// Empty rows removed.
+    pub dropped: usize,
+}
+
+impl NotePreprocessor {
+    /// # Errors
+    /// Returns [`Error::UnsortedNotes`] if `sort` is off and the rows are out of order.
+    pub fn process(&self, notes: &[Note]) -> Result<Preprocessed, Error> {
+        let mut out = Preprocessed {
+            notes: notes.to_vec(),
+            ..Preprocessed::default()
+        };
+
+        if self.drop_empty {
+            out.notes.retain(|n| n.notes != 0);
+            out.dropped = notes.len() - out.notes.len();
+        }
+
+        // checked against the caller's rows so the index points into their input
+        let mut kept = notes
+            .iter()
+            .enumerate()
+            .filter(|(_, n)| !self.drop_empty || n.notes != 0);
+        let unsorted = kept.next().and_then(|(_, first)| {
+            let mut prev = first.row_time;
+            kept.find_map(|(i, n)| {
+                let out_of_order = n.row_time < prev;
+                prev = n.row_time;
+                out_of_order.then_some(i)
+            })
+        });
+        if let Some(index) = unsorted {
+            if !self.sort {
+                return Err(Error::UnsortedNotes { index });
+            }
+            out.notes.sort_by(|a, b| a.row_time.total_cmp(&b.row_time));
+            out.reordered = true;
+        }
+
+        if self.merge_duplicates {
+            let before = out.notes.len();
+            out.notes.dedup_by(|next, prev| {
+                if next.row_time == prev.row_time {
+                    prev.notes |= next.notes;
+                    true
+                } else {
+                    false
+                }
+            });
+            out.merged = before - out.notes.len();
+        }
+
+        if let Some(first) = out.notes.first().map(|n| n.row_time) {
+            out.offset = match self.start_at {
+                Some(start) => start - first,
+                None if self.clamp_negative && first < 0.0 => -first,
+                None => 0.0,
+            };
+            if out.offset != 0.0 {
+                for n in &mut out.notes {
+                    n.row_time += out.offset;
+                }
+            }
+        }
+
+        Ok(out)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn row(notes: u32, row_time: f32) -> Note {
+        Note { notes, row_time }
+    }
+
+    #[test]
+    fn unsorted_index_points_into_input() {
+        let p = NotePreprocessor {
+            sort: false,
+            ..NotePreprocessor::default()
+        };
+        let notes = [row(0, 0.0), row(0, 0.0), row(1, 1.0), row(1, 0.5)];
+        assert!(matches!(
+            p.process(&notes),
+            Err(Error::UnsortedNotes { index: 3 })
+        ));
+    }
+
+    #[test]
+    fn default_sorts_merges_and_drops() {
+        let notes = [row(1, 1.0), row(0, 0.2), row(2, -0.5), row(4, 1.0)];
+        let out = NotePreprocessor::default().process(&notes).unwrap();
+        let rows: Vec<_> = out.notes.iter().map(|n| (n.notes, n.row_time)).collect();
+        assert_eq!(rows, [(2, 0.0), (5, 1.5)]);
+        assert_eq!(out.offset, 0.5);
+        assert!(out.reordered);
+        assert_eq!((out.merged, out.dropped), (1, 1));
+    }
+}
@@ -0,0 +1,230 @@
+//! Etterna's Wife3 accuracy curve, for turning hit offsets into a score goal.
+
+use crate::calc::Calc;
+use crate::error::Error;
+use crate::types::{CalcMode, Note, ScoreGoal, SkillsetScores};
+
+/// Points for a tap hit dead on.
+pub const MAX_POINTS: f32 = 2.0;
+/// Points for a missed tap, also the floor for hits past the boo window.
+pub const MISS_WEIGHT: f32 = -5.5;
+
+/// Timing judge; J4 is Etterna's default.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum Judge {
+    J1,
+    J2,
+    J3,
+    #[default]
+    J4,
+    J5,
+    J6,
+    J7,
+    J8,
+    Justice,
+}
+
+impl Judge {
+    /// Multiplier applied to the J4 timing windows.
+    #[must_use]
+    pub const fn timing_scale(self) -> f32 {
+        match self {
+            Judge::J1 => 1.50,
+            Judge::J2 => 1.33,
+            Judge::J3 => 1.16,
+            Judge::J4 => 1.00,
+            Judge::J5 => 0.84,
+            Judge::J6 => 0.66,
+            Judge::J7 => 0.50,
+            Judge::J8 => 0.33,
+            Judge::Justice => 0.20,
+        }
+    }
+}
+
+/// Wife3 points for a tap hit `offset_ms` early or late, from
+/// [`MISS_WEIGHT`] up to [`MAX_POINTS`].
+///
+/// Pass an offset outside the window (e.g. `f32::INFINITY`) for a miss.
+#[must_use]
+pub fn wife3(offset_ms: f32, judge: Judge) -> f32 {
+    // keeps judge scaling from being too extreme
+    const J_POW: f32 = 0.75;
+    let ts = judge.timing_scale();
+    let ridic = 5.0 * ts;
+    let max_boo_weight = 180.0 * ts;
+    let offset = offset_ms.abs();
+
+    if offset <= ridic {
+        return MAX_POINTS;
+    }
+    let zero = 65.0 * ts.powf(J_POW);
+    let dev = 22.7 * ts.powf(J_POW);
+    if offset <= zero {
+        return MAX_POINTS * erf((zero - offset) / dev);
+    }
+    if offset <= max_boo_weight {
+        return (offset - zero) * MISS_WEIGHT / (max_boo_weight - zero);
+    }
+    MISS_WEIGHT
+}
+
+/// Wife3 accuracy over every tap, as a fraction (`0.93` for 93%).
+///
+/// Misses pull the result down and can take it below zero. Empty input
+/// scores zero.
+///
+/// ```
+/// use minacalc_rs::scoring::{score_from_offsets, Judge};
+///
+/// assert_eq!(score_from_offsets(&[0.0, 3.0, -4.0], Judge::J4), 1.0);
+/// assert!(score_from_offsets(&[0.0, f32::INFINITY], Judge::J4) < 0.0);
+/// ```
+#[must_use]
+pub fn score_from_offsets(offsets_ms: &[f32], judge: Judge) -> f32 {
+    if offsets_ms.is_empty() {
+        return 0.0;
+    }
+    let points: f32 = offsets_ms.iter().map(|&o| wife3(o, judge)).sum();
+    points / (offsets_ms.len() as f32 * MAX_POINTS)
+}
+
+/// Accuracy of a replay and the SSR it earns.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ReplayRating {
+    /// Wife3 accuracy as a fraction, see [`score_from_offsets`].
+    pub accuracy: f32,
+    /// Goal `ssr` was rated at: `accuracy` clamped to `0..=1`, then capped at
+    /// [`ScoreGoal::SSR_CAP`] like every SSR.
+    pub goal: ScoreGoal,
+    pub ssr: SkillsetScores,
+}
+
+/// Score a replay with Wife3 and rate the chart at the accuracy it reached.
+///
+/// `replay_hits` holds one offset in ms per note, in chart order (rows by
+/// time, columns left to right within a row), with `f32::INFINITY` for
+/// misses. Offsets are in real time, as recorded at `music_rate`.
+///
+/// The accuracy is clamped to `0..=1` before it becomes the score goal, and
+/// the calc caps that goal at 96.5%: a 99.9% replay earns the 96.5% SSR.
+/// [`ReplayRating::goal`] reports the goal actually used.
+///
+/// # Errors
+/// Returns [`Error::ReplayLengthMismatch`] if `replay_hits` doesn't have one
+/// entry per note, or any error from [`Calc::calc_at_rate`].
+pub fn ssr_from_replay(
+    calc: &Calc,
+    notes: &[Note],
+    replay_hits: &[f32],
+    music_rate: f32,
+    keys: u32,
+    judge: Judge,
+) -> Result<ReplayRating, Error> {
+    let expected = notes.iter().map(|n| n.notes.count_ones() as usize).sum();
+    if replay_hits.len() != expected {
+        return Err(Error::ReplayLengthMismatch {
+            expected,
+            actual: replay_hits.len(),
+        });
+    }
+    let accuracy = score_from_offsets(replay_hits, judge);
+    let score_goal = ScoreGoal::from_fraction(accuracy.clamp(0.0, 1.0))?;
+    let ssr = calc.calc_at_rate(notes, music_rate, keys, CalcMode::Ssr { score_goal })?;
+    Ok(ReplayRating {
+        accuracy,
+        goal: score_goal.effective(),
+        ssr,
+    })
+}
+
+/// Abramowitz & Stegun 7.1.26, within 1.5e-7 of the real thing.
+fn erf(x: f32) -> f32 {
+    let sign = x.signum();
+    let x = f64::from(x.abs());
+    let t = 1.0 / (1.0 + 0.327_591_1 * x);
+    let poly = t
+        * (0.254_829_592
+            + t * (-0.284_496_736
+                + t * (1.421_413_741 + t * (-1.453_152_027 + t * 1.061_405_429))));
+    sign * (1.0 - poly * (-x * x).exp()) as f32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 8 rows a second, every fourth row a jump.
+    fn chart(seconds: f32) -> Vec<Note> {
+        (0..(seconds * 8.0) as usize)
+            .map(|i| Note {
+                notes: if i % 4 == 0 { 0b0011 } else { 1 << (i % 4) },
+                row_time: i as f32 / 8.0,
+            })
+            .collect()
+    }
+
+    fn hits(notes: &[Note]) -> usize {
+        notes.iter().map(|n| n.notes.count_ones() as usize).sum()
+    }
+
+    #[test]
+    fn wife3_window_edges() {
+        assert_eq!(wife3(0.0, Judge::J4), MAX_POINTS);
+        assert_eq!(wife3(-5.0, Judge::J4), MAX_POINTS);
+        assert!(wife3(65.0, Judge::J4).abs() < 1e-5);
+        assert!((wife3(180.0, Judge::J4) - MISS_WEIGHT).abs() < 1e-5);
+        assert_eq!(wife3(f32::INFINITY, Judge::J4), MISS_WEIGHT);
+    }
+
+    #[test]
+    fn wife3_scales_with_judge() {
+        let j4 = wife3(40.0, Judge::J4);
+        let j7 = wife3(40.0, Judge::J7);
+        assert!(j4 > 1.5, "{j4}");
+        // J7's zero point is ~38.7ms, so 40ms already costs points
+        assert!(j7 < 0.0, "{j7}");
+        assert_eq!(wife3(40.0, Judge::J7), wife3(-40.0, Judge::J7));
+    }
+
+    #[test]
+    fn replay_needs_one_hit_per_note() {
+        let calc = Calc::new().unwrap();
+        let notes = chart(30.0);
+        // one offset per row falls short, jumps take two
+        let per_row = vec![0.0; notes.len()];
+        let result = ssr_from_replay(&calc, &notes, &per_row, 1.0, 4, Judge::J4);
+        assert!(matches!(
+            result,
+            Err(Error::ReplayLengthMismatch { expected, actual })
+                if expected == hits(&notes) && actual == notes.len()
+        ));
+    }
+
+    #[test]
+    fn replay_goal_is_capped() {
+        let calc = Calc::new().unwrap();
+        let notes = chart(30.0);
+        let replay = vec![7.0; hits(&notes)];
+        let rating = ssr_from_replay(&calc, &notes, &replay, 1.0, 4, Judge::J4).unwrap();
+        assert!(rating.accuracy > 0.999 && rating.accuracy < 1.0);
+        assert_eq!(rating.goal, ScoreGoal::SSR_CAP);
+        let capped = CalcMode::Ssr {
+            score_goal: ScoreGoal::SSR_CAP,
+        };
+        assert_eq!(
+            rating.ssr,
+            calc.calc_at_rate(&notes, 1.0, 4, capped).unwrap()
+        );
+    }
+
+    #[test]
+    fn all_miss_replay_clamps_to_zero_goal() {
+        let calc = Calc::new().unwrap();
+        let notes = chart(30.0);
+        let replay = vec![f32::INFINITY; hits(&notes)];
+        let rating = ssr_from_replay(&calc, &notes, &replay, 1.0, 4, Judge::J4).unwrap();
+        assert!(rating.accuracy < 0.0);
+        assert_eq!(rating.goal, ScoreGoal::from_fraction(0.0).unwrap());
+    }
+}
@@ -0,0 +1,135 @@
+use crate::types::Note;
+
+/// Length and density figures for a chart, typically shown next to its MSD.
+///
+/// Assumes rows are sorted by time. Note counts are individual notes, so a
+/// jump counts as two.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ChartStats {
+    /// Seconds from first to last row.
+    pub length: f32,
+    pub note_count: usize,
+    pub row_count: usize,
+    /// `chord_counts[n]` is the number of rows with exactly `n` notes.
+    pub chord_counts: Vec<usize>,
+    /// Notes per second over the whole chart.
+    pub average_nps: f32,
+    /// Highest notes per second within any window of [`ChartStats::window`].
+    pub peak_nps: f32,
+    /// Window length used for `peak_nps`, in seconds.
+    pub window: f32,
+}
+
+impl ChartStats {
+    /// Window used by [`ChartStats::from_notes`].
+    pub const DEFAULT_WINDOW: f32 = 1.0;
+
+    #[must_use]
+    pub fn from_notes(notes: &[Note]) -> Self {
+        Self::with_window(notes, Self::DEFAULT_WINDOW)
+    }
+
+    /// Same as [`ChartStats::from_notes`] with a custom peak NPS window (seconds).
+    ///
+    /// A window that isn't positive gives a `peak_nps` of 0.
+    #[must_use]
+    pub fn with_window(notes: &[Note], window: f32) -> Self {
+        let (Some(first), Some(last)) = (notes.first(), notes.last()) else {
+            return Self {
+                window,
+                ..Self::default()
+            };
+        };
+
+        let mut chord_counts = Vec::new();
+        let mut note_count = 0;
+        for n in notes {
+            let size = n.notes.count_ones() as usize;
+            if chord_counts.len() <= size {
+                chord_counts.resize(size + 1, 0);
+            }
+            chord_counts[size] += 1;
+            note_count += size;
+        }
+
+        let length = last.row_time - first.row_time;
+        let average_nps = if length > 0.0 {
+            note_count as f32 / length
+        } else {
+            0.0
+        };
+
+        if window.is_nan() || window <= 0.0 {
+            return Self {
+                length,
+                note_count,
+                row_count: notes.len(),
+                chord_counts,
+                average_nps,
+                peak_nps: 0.0,
+                window,
+            };
+        }
+
+        // two pointers over rows: notes in [notes[start].row_time, +window)
+        let mut peak = 0;
+        let mut in_window = 0;
+        let mut start = 0;
+        for end in notes {
+            in_window += end.notes.count_ones() as usize;
+            while end.row_time - notes[start].row_time >= window {
+                in_window -= notes[start].notes.count_ones() as usize;
+                start += 1;
+            }
+            peak = peak.max(in_window);
+        }
+
+        Self {
+            length,
+            note_count,
+            row_count: notes.len(),
+            chord_counts,
+            average_nps,
+            peak_nps: peak as f32 / window,
+            window,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn stream(rows: usize, spacing: f32) -> Vec<Note> {
+        (0..rows)
+            .map(|i| Note {
+                notes: 1 << (i % 4),
+                row_time: i as f32 * spacing,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn non_positive_window_has_no_peak() {
+        let notes = stream(10, 0.1);
+        for window in [0.0, -1.0, f32::NAN] {
+            let stats = ChartStats::with_window(&notes, window);
+            assert_eq!(stats.peak_nps, 0.0);
+            assert_eq!(stats.note_count, 10);
+        }
+    }
+
+    #[test]
+    fn peak_uses_densest_window() {
+        // 4 rows over the first second, then a jump every 0.1s
+        let mut notes = stream(4, 0.25);
+        notes.extend((0..10).map(|i| Note {
+            notes: 0b0011,
+            row_time: 2.0 + i as f32 * 0.1,
+        }));
+        let stats = ChartStats::from_notes(&notes);
+        assert_eq!(stats.peak_nps, 20.0);
+        assert_eq!(stats.chord_counts, [0, 4, 10]);
+        assert_eq!(stats.note_count, 24);
+    }
+}
@@ -0,0 +1,163 @@
+//! Column transforms over [`Note`] rows.
+//!
+//! Every transform returns new rows and leaves row times untouched.
+
+use crate::error::Error;
+use crate::types::Note;
+
+/// Flip the chart left to right: column `c` becomes `keys - 1 - c`.
+///
+/// # Errors
+/// Returns [`Error::ColumnOutOfRange`] if `keys` is over 32 or a note sits on
+/// a column not below `keys`.
+pub fn mirror(notes: &[Note], keys: u32) -> Result<Vec<Note>, Error> {
+    check_keys(keys)?;
+    let mapping: Vec<usize> = (0..keys as usize).rev().collect();
+    remap(notes, &mapping)
+}
+
+/// Move column `c` to `mapping[c]`.
+///
+/// Columns mapped to the same target are merged into one tap.
+///
+/// ```
+/// use minacalc_rs::{transforms, Note};
+///
+/// let notes = [Note { notes: 0b0011, row_time: 0.0 }];
+/// let swapped = transforms::remap(&notes, &[1, 0, 2, 3]).unwrap();
+/// assert_eq!(swapped[0].notes, 0b0011);
+/// let moved = transforms::remap(&notes, &[2, 3, 0, 1]).unwrap();
+/// assert_eq!(moved[0].notes, 0b1100);
+/// ```
+///
+/// # Errors
+/// Returns [`Error::ColumnOutOfRange`] if a note sits on a column with no
+/// entry in `mapping`, or if a target column is 32 or more.
+pub fn remap(notes: &[Note], mapping: &[usize]) -> Result<Vec<Note>, Error> {
+    if let Some(&target) = mapping.iter().find(|&&target| target >= 32) {
+        return Err(Error::ColumnOutOfRange {
+            column: target as u32,
+            keys: 32,
+        });
+    }
+    notes
+        .iter()
+        .map(|note| {
+            let mut bits = note.notes;
+            let mut remapped = 0;
+            while bits != 0 {
+                let column = bits.trailing_zeros();
+                bits &= bits - 1;
+                let target = mapping
+                    .get(column as usize)
+                    .ok_or(Error::ColumnOutOfRange {
+                        column,
+                        keys: mapping.len() as u32,
+                    })?;
+                remapped |= 1 << target;
+            }
+            Ok(Note {
+                notes: remapped,
+                row_time: note.row_time,
+            })
+        })
+        .collect()
+}
+
+/// Shuffle the first `keys` columns with a permutation derived from `seed`.
+///
+/// The same seed always yields the same permutation, which is returned
+/// alongside the rows so it can be reapplied with [`remap`].
+///
+/// # Errors
+/// Returns [`Error::ColumnOutOfRange`] if `keys` is over 32 or a note sits on
+/// a column not below `keys`.
+pub fn column_shuffle(
+    notes: &[Note],
+    keys: u32,
+    seed: u64,
+) -> Result<(Vec<Note>, Vec<usize>), Error> {
+    check_keys(keys)?;
+    let mut mapping: Vec<usize> = (0..keys as usize).collect();
+    // splitmix64, enough to pick a permutation without pulling in `rand`
+    let mut state = seed;
+    let mut next = || {
+        state = state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    };
+    for i in (1..mapping.len()).rev() {
+        let j = (next() % (i as u64 + 1)) as usize;
+        mapping.swap(i, j);
+    }
+    let notes = remap(notes, &mapping)?;
+    Ok((notes, mapping))
+}
+
+/// Rows are 32-bit masks, so there is no column past 31 to map.
+fn check_keys(keys: u32) -> Result<(), Error> {
+    if keys > 32 {
+        return Err(Error::ColumnOutOfRange {
+            column: keys - 1,
+            keys: 32,
+        });
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rows(notes: &[Note]) -> Vec<(u32, f32)> {
+        notes.iter().map(|n| (n.notes, n.row_time)).collect()
+    }
+
+    fn chart() -> Vec<Note> {
+        [0b0001, 0b0110, 0b1000, 0b1011]
+            .into_iter()
+            .enumerate()
+            .map(|(i, notes)| Note {
+                notes,
+                row_time: i as f32 * 0.25,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn mirror_twice_is_identity() {
+        let notes = chart();
+        let mirrored = mirror(&notes, 4).unwrap();
+        assert_eq!(mirrored[0].notes, 0b1000);
+        assert_eq!(rows(&mirror(&mirrored, 4).unwrap()), rows(&notes));
+    }
+
+    #[test]
+    fn shuffle_is_deterministic_per_seed() {
+        let notes = chart();
+        let (a, mapping) = column_shuffle(&notes, 4, 42).unwrap();
+        let (b, again) = column_shuffle(&notes, 4, 42).unwrap();
+        assert_eq!(mapping, again);
+        assert_eq!(rows(&a), rows(&b));
+        assert_eq!(rows(&remap(&notes, &mapping).unwrap()), rows(&a));
+
+        let mut sorted = mapping.clone();
+        sorted.sort_unstable();
+        assert_eq!(sorted, [0, 1, 2, 3]);
+    }
+
+    #[test]
+    fn too_many_keys_are_rejected() {
+        let notes = chart();
+        assert!(matches!(
+            mirror(&notes, u32::MAX),
+            Err(Error::ColumnOutOfRange { keys: 32, .. })
+        ));
+        assert!(matches!(
+            column_shuffle(&notes, 33, 0),
+            Err(Error::ColumnOutOfRange { keys: 32, .. })
+        ));
+    }
+}
@@ -1,3 +1,7 @@
+use std::fmt;
+
+use crate::error::Error;
+
 /// A single row of notes.
 /// `notes` is a bitmask of active columns, `row_time` is in seconds.
 #[derive(Debug, Clone, Copy)]
@@ -6,17 +10,8 @@ pub struct Note {
     pub row_time: f32,
 }
 
-impl From<Note> for minacalc_sys::NoteInfo {
-    fn from(n: Note) -> Self {
-        minacalc_sys::NoteInfo {
-            notes: n.notes,
-            rowTime: n.row_time,
-        }
-    }
-}
-
 /// Difficulty scores for each skillset.
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
 pub struct SkillsetScores {
     pub overall: f32,
     pub stream: f32,
@@ -28,49 +23,482 @@ pub struct SkillsetScores {
     pub technical: f32,
 }
 
-impl From<minacalc_sys::Ssr> for SkillsetScores {
-    fn from(s: minacalc_sys::Ssr) -> Self {
-        Self {
-            overall: s.overall,
-            stream: s.stream,
-            jumpstream: s.jumpstream,
-            handstream: s.handstream,
-            stamina: s.stamina,
-            jackspeed: s.jackspeed,
-            chordjack: s.chordjack,
-            technical: s.technical,
+/// One of the calc's skillsets, in the same order as the C++ `Skillset` enum.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Skillset {
+    Overall,
+    Stream,
+    Jumpstream,
+    Handstream,
+    Stamina,
+    Jackspeed,
+    Chordjack,
+    Technical,
+}
+
+impl Skillset {
+    pub const ALL: [Skillset; 8] = [
+        Skillset::Overall,
+        Skillset::Stream,
+        Skillset::Jumpstream,
+        Skillset::Handstream,
+        Skillset::Stamina,
+        Skillset::Jackspeed,
+        Skillset::Chordjack,
+        Skillset::Technical,
+    ];
+
+    /// Every skillset except [`Skillset::Overall`].
+    pub const PATTERNS: [Skillset; 7] = [
+        Skillset::Stream,
+        Skillset::Jumpstream,
+        Skillset::Handstream,
+        Skillset::Stamina,
+        Skillset::Jackspeed,
+        Skillset::Chordjack,
+        Skillset::Technical,
+    ];
+
+    /// Lowercase name, matching the [`SkillsetScores`] field.
+    #[must_use]
+    pub const fn name(self) -> &'static str {
+        match self {
+            Skillset::Overall => "overall",
+            Skillset::Stream => "stream",
+            Skillset::Jumpstream => "jumpstream",
+            Skillset::Handstream => "handstream",
+            Skillset::Stamina => "stamina",
+            Skillset::Jackspeed => "jackspeed",
+            Skillset::Chordjack => "chordjack",
+            Skillset::Technical => "technical",
         }
     }
 }
 
+impl fmt::Display for Skillset {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl SkillsetScores {
+    #[must_use]
+    pub const fn get(&self, skillset: Skillset) -> f32 {
+        match skillset {
+            Skillset::Overall => self.overall,
+            Skillset::Stream => self.stream,
+            Skillset::Jumpstream => self.jumpstream,
+            Skillset::Handstream => self.handstream,
+            Skillset::Stamina => self.stamina,
+            Skillset::Jackspeed => self.jackspeed,
+            Skillset::Chordjack => self.chordjack,
+            Skillset::Technical => self.technical,
+        }
+    }
+
+    /// All eight `(skillset, score)` pairs, overall first.
+    pub fn iter(&self) -> impl Iterator<Item = (Skillset, f32)> + '_ {
+        Skillset::ALL.into_iter().map(|ss| (ss, self.get(ss)))
+    }
+
+    /// Highest-scoring skillset, not counting overall.
+    #[must_use]
+    pub fn max_skillset(&self) -> (Skillset, f32) {
+        self.sorted_skillsets()[0]
+    }
+
+    /// Skillsets other than overall, hardest first.
+    #[must_use]
+    pub fn sorted_skillsets(&self) -> Vec<(Skillset, f32)> {
+        let mut sorted: Vec<_> = Skillset::PATTERNS
+            .into_iter()
+            .map(|ss| (ss, self.get(ss)))
+            .collect();
+        // stable, so ties keep the calc's skillset order
+        sorted.sort_by(|a, b| b.1.total_cmp(&a.1));
+        sorted
+    }
+
+    /// Per-skillset `self - other`.
+    #[must_use]
+    pub fn delta(&self, other: &SkillsetScores) -> SkillsetScores {
+        SkillsetScores {
+            overall: self.overall - other.overall,
+            stream: self.stream - other.stream,
+            jumpstream: self.jumpstream - other.jumpstream,
+            handstream: self.handstream - other.handstream,
+            stamina: self.stamina - other.stamina,
+            jackspeed: self.jackspeed - other.jackspeed,
+            chordjack: self.chordjack - other.chordjack,
+            technical: self.technical - other.technical,
+        }
+    }
+
+    /// Whether every skillset is within `epsilon` of `other`.
+    #[must_use]
+    pub fn approx_eq(&self, other: &SkillsetScores, epsilon: f32) -> bool {
+        self.delta(other).iter().all(|(_, d)| d.abs() <= epsilon)
+    }
+}
+
+/// A music rate multiplier (e.g. `1.0` for 1x).
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd)]
+pub struct Rate(f32);
+
+impl Rate {
+    /// Lowest rate covered by [`AllRates`].
+    pub const MIN: Rate = Rate(0.7);
+    /// Highest rate covered by [`AllRates`].
+    pub const MAX: Rate = Rate(2.0);
+    /// 1x, no rate mod.
+    pub const ONE: Rate = Rate(1.0);
+
+    #[must_use]
+    pub const fn new(rate: f32) -> Self {
+        Self(rate)
+    }
+
+    #[must_use]
+    pub const fn value(self) -> f32 {
+        self.0
+    }
+
+    /// Rate stored at `index` in [`AllRates::rates`] (0 = 0.7x, 13 = 2.0x).
+    #[must_use]
+    pub fn from_index(index: usize) -> Self {
+        // same integer-tenths math as the C++ side, so 1.0x is exactly 1.0
+        Self((index + 7) as f32 / 10.0)
+    }
+
+    /// Index of this rate in [`AllRates::rates`], if it is one of the 14 covered rates.
+    #[must_use]
+    pub fn index(self) -> Option<usize> {
+        let tenths = (self.0 * 10.0).round();
+        if (self.0 * 10.0 - tenths).abs() > 1e-3 || !(7.0..=20.0).contains(&tenths) {
+            return None;
+        }
+        Some(tenths as usize - 7)
+    }
+}
+
+impl fmt::Display for Rate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match f.precision() {
+            Some(p) => write!(f, "{:.*}x", p, self.0),
+            None => write!(f, "{}x", self.0),
+        }
+    }
+}
+
+impl From<f32> for Rate {
+    fn from(rate: f32) -> Self {
+        Self(rate)
+    }
+}
+
+impl From<Rate> for f32 {
+    fn from(rate: Rate) -> Self {
+        rate.0
+    }
+}
+
 /// Scores for all rates from 0.7x to 2.0x (14 rates, step 0.1).
 #[derive(Debug, Clone, Copy)]
 pub struct AllRates {
     pub rates: [SkillsetScores; 14],
 }
 
-impl From<minacalc_sys::MsdForAllRates> for AllRates {
-    fn from(m: minacalc_sys::MsdForAllRates) -> Self {
-        Self {
-            rates: m.msds.map(SkillsetScores::from),
+impl AllRates {
+    /// Scores at `rate`, if it is one of the 14 covered rates.
+    #[must_use]
+    pub fn at(&self, rate: impl Into<Rate>) -> Option<&SkillsetScores> {
+        rate.into().index().map(|i| &self.rates[i])
+    }
+
+    /// Scores at the covered rate closest to `rate`, clamping to 0.7x–2.0x.
+    ///
+    /// A NaN `rate` has no closest rate and gives 0.7x.
+    #[must_use]
+    pub fn get_nearest(&self, rate: impl Into<Rate>) -> (Rate, &SkillsetScores) {
+        let index = nearest_index(rate.into().value(), self.rates.len());
+        (Rate::from_index(index), &self.rates[index])
+    }
+
+    /// Scores at `rate` if it is within `tolerance` of a covered rate.
+    ///
+    /// # Errors
+    /// Returns [`Error::UnsupportedRate`], listing the covered rates, otherwise.
+    pub fn try_get_exact(
+        &self,
+        rate: impl Into<Rate>,
+        tolerance: f32,
+    ) -> Result<&SkillsetScores, Error> {
+        let rate = rate.into().value();
+        let index = nearest_index(rate, self.rates.len());
+        if (rate - Rate::from_index(index).value()).abs() <= tolerance {
+            Ok(&self.rates[index])
+        } else {
+            Err(Error::UnsupportedRate {
+                rate,
+                supported: self.rates().collect(),
+            })
+        }
+    }
+
+    /// Rates covered, in the same order as [`AllRates::rates`].
+    pub fn rates(&self) -> impl Iterator<Item = Rate> {
+        (0..self.rates.len()).map(Rate::from_index)
+    }
+
+    /// Iterate over `(rate, scores)` pairs from 0.7x to 2.0x.
+    #[must_use]
+    pub fn iter(&self) -> AllRatesIter<'_> {
+        AllRatesIter {
+            inner: self.rates.iter().enumerate(),
+        }
+    }
+}
+
+fn nearest_index(rate: f32, len: usize) -> usize {
+    // NaN ends up at 0 through the saturating cast
+    ((rate * 10.0).round() - 7.0).clamp(0.0, (len - 1) as f32) as usize
+}
+
+/// Iterator over `(Rate, SkillsetScores)` pairs, created by [`AllRates::iter`].
+#[derive(Debug, Clone)]
+pub struct AllRatesIter<'a> {
+    inner: std::iter::Enumerate<std::slice::Iter<'a, SkillsetScores>>,
+}
+
+impl Iterator for AllRatesIter<'_> {
+    type Item = (Rate, SkillsetScores);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner
+            .next()
+            .map(|(i, &scores)| (Rate::from_index(i), scores))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl ExactSizeIterator for AllRatesIter<'_> {}
+
+impl<'a> IntoIterator for &'a AllRates {
+    type Item = (Rate, SkillsetScores);
+    type IntoIter = AllRatesIter<'a>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+/// Scores together with the inputs they were computed from.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RatedChart {
+    pub scores: SkillsetScores,
+    pub rate: Rate,
+    /// Mode, including the score goal for SSR.
+    pub mode: CalcMode,
+    pub keys: u32,
+    /// Individual notes (a jump counts as two).
+    pub note_count: usize,
+    /// Seconds from first to last row, at 1x.
+    pub length: f32,
+    /// Caller-provided chart identifier, see [`RatedChart::with_chartkey`].
+    pub chartkey: Option<String>,
+}
+
+impl RatedChart {
+    #[must_use]
+    pub fn with_chartkey(mut self, chartkey: impl Into<String>) -> Self {
+        self.chartkey = Some(chartkey.into());
+        self
+    }
+
+    /// Length in seconds as played at [`RatedChart::rate`].
+    #[must_use]
+    pub fn rated_length(&self) -> f32 {
+        self.length / self.rate.value()
+    }
+}
+
+/// Stamina multiplier over time for each hand.
+///
+/// One value per 0.5s interval of rated time (`row_time / rate`). Values start
+/// around 0.95 and climb (up to ~1.1) as the stamina wall builds up.
+#[derive(Debug, Clone, Default)]
+pub struct StaminaCurve {
+    pub left: Vec<f32>,
+    pub right: Vec<f32>,
+}
+
+impl StaminaCurve {
+    /// Length of one interval in seconds.
+    pub const INTERVAL: f32 = 0.5;
+
+    /// Number of intervals.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.left.len()
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.left.is_empty()
+    }
+
+    /// Iterate over `(time, left, right)`, `time` being the interval start in rated seconds.
+    pub fn iter(&self) -> impl Iterator<Item = (f32, f32, f32)> + '_ {
+        self.left
+            .iter()
+            .zip(&self.right)
+            .enumerate()
+            .map(|(i, (&l, &r))| (i as f32 * Self::INTERVAL, l, r))
+    }
+
+    /// First interval reaching the highest multiplier on either hand, as `(time, multiplier)`.
+    #[must_use]
+    pub fn peak(&self) -> Option<(f32, f32)> {
+        self.iter()
+            .map(|(t, l, r)| (t, l.max(r)))
+            .reduce(|best, cur| if cur.1 > best.1 { cur } else { best })
+    }
+}
+
+/// Accuracy a score-relative rating is computed for.
+///
+/// Stored as a fraction (`0.93` = 93%). Construct it explicitly from a
+/// percentage or a fraction so the two scales can't be mixed up.
+///
+/// The calc rates SSRs at no more than [`ScoreGoal::SSR_CAP`]: any goal above
+/// 96.5% gives the same scores as 96.5%. See [`ScoreGoal::effective`].
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd)]
+pub struct ScoreGoal(f32);
+
+impl ScoreGoal {
+    /// 93%, the goal Etterna uses for MSD.
+    pub const DEFAULT: ScoreGoal = ScoreGoal(0.93);
+
+    /// Highest goal the calc rates SSRs at (`ssr_goal_cap` in MinaCalc).
+    pub const SSR_CAP: ScoreGoal = ScoreGoal(0.965);
+
+    /// From a percentage, e.g. `93.0`.
+    ///
+    /// # Errors
+    /// Returns [`Error::InvalidScoreGoal`] unless `percent` is within `0..=100`.
+    pub fn from_percent(percent: f32) -> Result<Self, Error> {
+        if !(0.0..=100.0).contains(&percent) {
+            return Err(Error::InvalidScoreGoal { value: percent });
+        }
+        Ok(Self(percent / 100.0))
+    }
+
+    /// From a fraction, e.g. `0.93`.
+    ///
+    /// # Errors
+    /// Returns [`Error::InvalidScoreGoal`] unless `fraction` is within `0..=1`.
+    pub fn from_fraction(fraction: f32) -> Result<Self, Error> {
+        if !(0.0..=1.0).contains(&fraction) {
+            return Err(Error::InvalidScoreGoal { value: fraction });
+        }
+        Ok(Self(fraction))
+    }
+
+    #[must_use]
+    pub const fn fraction(self) -> f32 {
+        self.0
+    }
+
+    #[must_use]
+    pub fn percent(self) -> f32 {
+        self.0 * 100.0
+    }
+
+    /// The goal the calc actually rates an SSR at, capped to [`ScoreGoal::SSR_CAP`].
+    #[must_use]
+    pub fn effective(self) -> ScoreGoal {
+        ScoreGoal(self.0.min(Self::SSR_CAP.0))
+    }
+}
+
+impl Default for ScoreGoal {
+    fn default() -> Self {
+        Self::DEFAULT
+    }
+}
+
+impl fmt::Display for ScoreGoal {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match f.precision() {
+            Some(p) => write!(f, "{:.*}%", p, self.percent()),
+            None => write!(f, "{}%", self.percent()),
         }
     }
 }
 
 /// Calculation mode.
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub enum CalcMode {
     /// Raw difficulty, uncapped.
     Msd,
-    /// Score-relative difficulty, capped (score goal applies).
-    Ssr,
+    /// Score-relative difficulty, capped, for a score goal.
+    Ssr { score_goal: ScoreGoal },
+}
+
+impl CalcMode {
+    /// Goal the calc rates against: `score_goal` for SSR, 93% for MSD.
+    #[must_use]
+    pub const fn score_goal(self) -> ScoreGoal {
+        match self {
+            CalcMode::Msd => ScoreGoal::DEFAULT,
+            CalcMode::Ssr { score_goal } => score_goal,
+        }
+    }
 }
 
-impl From<CalcMode> for minacalc_sys::CalcMode {
-    fn from(m: CalcMode) -> Self {
-        match m {
-            CalcMode::Msd => minacalc_sys::CalcMode::MSD,
-            CalcMode::Ssr => minacalc_sys::CalcMode::SSR,
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn all_rates() -> AllRates {
+        let mut all = AllRates {
+            rates: [SkillsetScores::default(); 14],
+        };
+        for (i, scores) in all.rates.iter_mut().enumerate() {
+            scores.overall = i as f32;
+        }
+        all
+    }
+
+    #[test]
+    fn rate_index_snaps_to_tenths() {
+        assert_eq!(Rate::new(0.7 + 3.0 * 0.1).index(), Some(3));
+        assert_eq!(Rate::MIN.index(), Some(0));
+        assert_eq!(Rate::MAX.index(), Some(13));
+        assert_eq!(Rate::new(1.15).index(), None);
+        assert_eq!(Rate::new(2.1).index(), None);
+    }
+
+    #[test]
+    fn all_rates_lookups() {
+        let all = all_rates();
+        assert!(all.at(1.15).is_none());
+        assert_eq!(all.at(Rate::ONE).unwrap().overall, 3.0);
+
+        let (rate, scores) = all.get_nearest(5.0);
+        assert_eq!((rate, scores.overall), (Rate::MAX, 13.0));
+        let (rate, _) = all.get_nearest(0.1);
+        assert_eq!(rate, Rate::MIN);
+        let (rate, _) = all.get_nearest(f32::NAN);
+        assert_eq!(rate, Rate::MIN);
+
+        assert_eq!(all.try_get_exact(1.205, 0.01).unwrap().overall, 5.0);
+        match all.try_get_exact(1.15, 0.01) {
+            Err(Error::UnsupportedRate { supported, .. }) => assert_eq!(supported.len(), 14),
+            other => panic!("expected UnsupportedRate, got {other:?}"),
         }
     }
 }
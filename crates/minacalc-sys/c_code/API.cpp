@@ -1,4 +1,6 @@
 #include "MinaCalc/MinaCalc.h"
+#include <algorithm>
+#include <string>
 #include <vector>
 
 extern "C" {
@@ -69,4 +71,34 @@ extern "C" {
 		return skillset_vector_to_ssr(skillsets);
 	}
 
+	size_t calc_stamina_curve(CalcHandle *calc, const NoteInfo *rows, size_t num_rows, float music_rate, float score_goal, unsigned int keycount, float *left, float *right, size_t capacity) {
+		// the calc skips charts past max_intervals, but its int cast of the
+		// interval index (0.5s each) overflows first on far-off rows, so check in float
+		if (num_rows > 0 && rows[num_rows - 1].rowTime / music_rate / 0.5F >= max_intervals) {
+			return 0;
+		}
+
+		std::vector<NoteInfo> note_info(rows, rows + num_rows);
+		std::vector<std::vector<std::vector<std::vector<float>>>> hand_info;
+		std::vector<std::string> debug_strings;
+
+		auto *c = reinterpret_cast<Calc*>(calc);
+		// debug values persist on the calc, don't report a previous chart's curve
+		c->debugValues = {};
+		MinaSDCalcDebug(note_info, music_rate, score_goal, keycount, hand_info, debug_strings, *c);
+
+		if (hand_info.size() < 2 || hand_info[0].size() < 3 || hand_info[1].size() < 3) {
+			return 0;
+		}
+
+		const auto &left_stam = hand_info[0][2][StamMod];
+		const auto &right_stam = hand_info[1][2][StamMod];
+		const size_t count = std::min(left_stam.size(), right_stam.size());
+		const size_t written = std::min(count, capacity);
+		std::copy_n(left_stam.begin(), written, left);
+		std::copy_n(right_stam.begin(), written, right);
+
+		return count;
+	}
+
 }
@@ -46,6 +46,10 @@ MsdForAllRates calc_all_rates(CalcHandle *calc, const NoteInfo *rows, size_t num
 // score_goal: relevant for SSR (usually 0.93), ignored for MSD
 Ssr calc_at_rate(CalcHandle *calc, NoteInfo *rows, size_t num_rows, float music_rate, float score_goal, unsigned int keycount, CalcMode mode);
 
+// Stamina multiplier per 0.5s interval (of rated time) for each hand, from the calc's debug pass
+// Writes at most `capacity` values to `left` and `right`, returns the number of intervals
+size_t calc_stamina_curve(CalcHandle *calc, const NoteInfo *rows, size_t num_rows, float music_rate, float score_goal, unsigned int keycount, float *left, float *right, size_t capacity);
+
 #ifdef __cplusplus
 }
 #endif
@@ -171,6 +171,15 @@ Calc::CalcMain(const std::vector<NoteInfo>& NoteInfo,
 					   true,
 					   true);
 			}
+			// shared stam/pts debug values are overwritten by every pass,
+			// finish on the highest stam adjusted skillset so they describe it
+			Chisel(iteration_skillet_values.at(highest_stam_adjusted_skillset) -
+					 0.16F,
+				   0.32F,
+				   score_goal,
+				   highest_stam_adjusted_skillset,
+				   true,
+				   true);
 		}
 
 		// scale the output values to values familiar to the 4k calc